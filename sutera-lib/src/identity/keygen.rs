@@ -0,0 +1,203 @@
+use hmac::{Hmac, Mac};
+use pbkdf2::pbkdf2_hmac;
+use ring_compat::signature::ed25519;
+use sha2::Sha512;
+use thiserror::Error;
+
+use crate::signature::identity::{SuteraIdentity, SuteraIdentityKind, SuteraPublicKey};
+
+type HmacSha512 = Hmac<Sha512>;
+
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// An error that occurs when deriving an identity from a BIP39 mnemonic.
+/// BIP39のmnemonicからidentityを導出する際に起きるエラー。
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SuteraMnemonicKeygenError {
+    #[error("mnemonic phrase must contain 12 or 24 words, found {0}")]
+    InvalidWordCount(usize),
+    #[error("derivation path must not be empty")]
+    EmptyPath,
+}
+
+/// Derive the 64-byte BIP39 seed from a mnemonic phrase and an optional
+/// passphrase, via PBKDF2-HMAC-SHA512 with 2048 iterations and salt
+/// `"mnemonic" + passphrase`, as specified by BIP39.
+/// mnemonicと任意のpassphraseから、BIP39で規定されている通り
+/// PBKDF2-HMAC-SHA512(2048回のイテレーション, salt = `"mnemonic" + passphrase`)を用いて
+/// 64byteのseedを導出します。
+fn mnemonic_to_seed(
+    phrase: &str,
+    passphrase: &str,
+) -> Result<[u8; 64], SuteraMnemonicKeygenError> {
+    let word_count = phrase.split_whitespace().count();
+    if word_count != 12 && word_count != 24 {
+        return Err(SuteraMnemonicKeygenError::InvalidWordCount(word_count));
+    }
+
+    let salt = format!("mnemonic{}", passphrase);
+    let mut seed = [0u8; 64];
+    pbkdf2_hmac::<Sha512>(phrase.as_bytes(), salt.as_bytes(), 2048, &mut seed);
+    Ok(seed)
+}
+
+/// Derive the SLIP-0010 ed25519 master key and chain code from a BIP39 seed:
+/// `I = HMAC-SHA512(key = "ed25519 seed", data = seed)`.
+/// BIP39のseedからSLIP-0010のed25519マスターキーとチェーンコードを導出します。
+fn master_key(seed: &[u8; 64]) -> ([u8; 32], [u8; 32]) {
+    let mut mac =
+        HmacSha512::new_from_slice(b"ed25519 seed").expect("HMAC accepts keys of any size");
+    mac.update(seed);
+    split_hmac_output(&mac.finalize().into_bytes())
+}
+
+/// Derive a single SLIP-0010 hardened ed25519 child key:
+/// `I = HMAC-SHA512(key = chain_code, data = 0x00 || key || ser32(index | 0x80000000))`.
+/// All ed25519 SLIP-0010 indices are hardened, so the hardened bit is always set here.
+/// SLIP-0010のハードened ed25519子鍵を1つ導出します。
+/// ed25519のSLIP-0010では全てのindexがhardenedであるため、ここで常にhardenedビットを立てます。
+fn derive_child(key: &[u8; 32], chain_code: &[u8; 32], index: u32) -> ([u8; 32], [u8; 32]) {
+    let index = index | HARDENED_OFFSET;
+
+    let mut mac =
+        HmacSha512::new_from_slice(chain_code).expect("HMAC accepts keys of any size");
+    mac.update(&[0u8]);
+    mac.update(key);
+    mac.update(&index.to_be_bytes());
+    split_hmac_output(&mac.finalize().into_bytes())
+}
+
+fn split_hmac_output(output: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let mut left = [0u8; 32];
+    let mut right = [0u8; 32];
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..64]);
+    (left, right)
+}
+
+/// Derive an ed25519 signing key along an SLIP-0010 hardened derivation path
+/// starting from a BIP39 seed. `path` is given as plain indices, e.g.
+/// `&[44, 1312, 0, 0]` for `m/44'/1312'/0'/0'`.
+/// BIP39のseedからSLIP-0010のhardened derivation pathに沿ってed25519署名鍵を導出します。
+/// `path`は`m/44'/1312'/0'/0'`であれば`&[44, 1312, 0, 0]`のようにプレーンなindexで指定します。
+fn derive_path(
+    seed: &[u8; 64],
+    path: &[u32],
+) -> Result<ed25519::SigningKey, SuteraMnemonicKeygenError> {
+    if path.is_empty() {
+        return Err(SuteraMnemonicKeygenError::EmptyPath);
+    }
+
+    let (mut key, mut chain_code) = master_key(seed);
+    for index in path {
+        let (child_key, child_chain_code) = derive_child(&key, &chain_code, *index);
+        key = child_key;
+        chain_code = child_chain_code;
+    }
+
+    Ok(ed25519::SigningKey::from_seed(&key).expect("SLIP-0010 derives a 32-byte seed"))
+}
+
+impl SuteraIdentity {
+    /// Deterministically derive a [`SuteraIdentity`] and its ed25519 signing key
+    /// from a BIP39 mnemonic phrase (12 or 24 words), an optional passphrase,
+    /// and an SLIP-0010 hardened derivation path, so that the keypair can be
+    /// backed up as a human-readable phrase and restored offline.
+    /// BIP39のmnemonic(12語または24語), 任意のpassphrase, SLIP-0010のhardened derivation pathから、
+    /// [`SuteraIdentity`]とそのed25519署名鍵を決定的に導出します。
+    /// これにより、鍵ペアを人間が復元可能なフレーズとしてバックアップし、オフラインで復元できます。
+    pub fn from_mnemonic(
+        phrase: &str,
+        passphrase: &str,
+        kind: SuteraIdentityKind,
+        display_name: Option<String>,
+        path: &[u32],
+    ) -> Result<(SuteraIdentity, ed25519::SigningKey), SuteraMnemonicKeygenError> {
+        let seed = mnemonic_to_seed(phrase, passphrase)?;
+        let signing_key = derive_path(&seed, path)?;
+
+        let identity = SuteraIdentity {
+            kind,
+            display_name,
+            pub_signature: SuteraPublicKey::Ed25519(signing_key.verifying_key()),
+        };
+
+        Ok((identity, signing_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_MNEMONIC: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn from_mnemonic_is_deterministic() {
+        use ring_compat::signature::Signer;
+
+        let (identity_a, signing_key_a) = SuteraIdentity::from_mnemonic(
+            TEST_MNEMONIC,
+            "",
+            SuteraIdentityKind::User,
+            Some("see2et".to_string()),
+            &[44, 1312, 0, 0],
+        )
+        .unwrap();
+
+        let (identity_b, signing_key_b) = SuteraIdentity::from_mnemonic(
+            TEST_MNEMONIC,
+            "",
+            SuteraIdentityKind::User,
+            Some("see2et".to_string()),
+            &[44, 1312, 0, 0],
+        )
+        .unwrap();
+
+        assert_eq!(identity_a, identity_b);
+        assert_eq!(
+            signing_key_a.sign(b"probe"),
+            signing_key_b.sign(b"probe")
+        );
+    }
+
+    #[test]
+    fn from_mnemonic_different_passphrase_differs() {
+        let (identity_a, _) = SuteraIdentity::from_mnemonic(
+            TEST_MNEMONIC,
+            "",
+            SuteraIdentityKind::User,
+            None,
+            &[44, 1312, 0, 0],
+        )
+        .unwrap();
+
+        let (identity_b, _) = SuteraIdentity::from_mnemonic(
+            TEST_MNEMONIC,
+            "a different passphrase",
+            SuteraIdentityKind::User,
+            None,
+            &[44, 1312, 0, 0],
+        )
+        .unwrap();
+
+        assert_ne!(identity_a, identity_b);
+    }
+
+    #[test]
+    fn from_mnemonic_rejects_invalid_word_count() {
+        let result = SuteraIdentity::from_mnemonic(
+            "only six words in this phrase",
+            "",
+            SuteraIdentityKind::User,
+            None,
+            &[44, 1312, 0, 0],
+        );
+
+        assert_eq!(
+            result.err(),
+            Some(SuteraMnemonicKeygenError::InvalidWordCount(6))
+        );
+    }
+}
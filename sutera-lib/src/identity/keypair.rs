@@ -0,0 +1,193 @@
+use std::fs;
+use std::path::Path;
+
+use rand_core::{OsRng, RngCore};
+use ring_compat::signature::ed25519;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::signature::identity::{SuteraIdentity, SuteraIdentityKind, SuteraPublicKey};
+
+/// An error that occurs when constructing, encoding, or persisting a [`Keypair`].
+/// [`Keypair`]の構築・エンコード・永続化の際に起きるエラー。
+#[derive(Debug, Error)]
+pub enum SuteraKeypairError {
+    #[error("keypair bytes must be exactly 32 bytes (the ed25519 signing seed), found {0}")]
+    InvalidLength(usize),
+    #[error("invalid base58 keypair string")]
+    InvalidBase58(#[from] bs58::decode::Error),
+    #[error("failed to read or write the keypair file")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse the keypair file")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A signing keypair, together with its cached verifying key, so applications
+/// never have to juggle raw `ed25519::SigningKey` bytes by hand.
+///
+/// `ring_compat`'s `ed25519::SigningKey` never hands its seed back out (it only
+/// exposes `verifying_key()`/`sign()`), so this also keeps the seed it was
+/// built from around, both to serve as this keypair's wire format and because
+/// it is the only material callers who need X25519 key agreement can derive
+/// from.
+/// 署名鍵とキャッシュされた認証鍵を保持するコンテナ。
+/// アプリケーションが生の`ed25519::SigningKey`のバイト列を直接扱う必要がなくなります。
+/// `ring_compat`の`ed25519::SigningKey`はseedを外部に公開しない
+/// (`verifying_key()`/`sign()`のみを提供する)ため、このコンテナは構築時のseedも
+/// 保持しています。これはこのkeypairのワイヤー形式であると同時に、X25519鍵交換を
+/// 必要とする呼び出し側が導出できる唯一の材料でもあります。
+pub struct Keypair {
+    seed: [u8; 32],
+    signing_key: ed25519::SigningKey,
+    verifying_key: ed25519::VerifyingKey,
+}
+
+impl Keypair {
+    /// Generate a new keypair using the operating system's secure RNG.
+    /// OSの安全なRNGを用いて新しいkeypairを生成します。
+    pub fn generate() -> Self {
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        Self::from_seed(seed)
+    }
+
+    fn from_seed(seed: [u8; 32]) -> Self {
+        let signing_key =
+            ed25519::SigningKey::from_seed(&seed).expect("seed is exactly 32 bytes");
+        let verifying_key = signing_key.verifying_key();
+
+        Keypair {
+            seed,
+            signing_key,
+            verifying_key,
+        }
+    }
+
+    pub fn signing_key(&self) -> &ed25519::SigningKey {
+        &self.signing_key
+    }
+
+    pub fn verifying_key(&self) -> &ed25519::VerifyingKey {
+        &self.verifying_key
+    }
+
+    /// The raw 32-byte ed25519 signing seed this keypair was derived from.
+    /// このkeypairの導出元である生の32byte ed25519 seed。
+    pub fn seed(&self) -> &[u8; 32] {
+        &self.seed
+    }
+
+    /// Encode as the 32-byte ed25519 signing seed, from which the verifying
+    /// key can be re-derived.
+    /// このkeypairの32byte ed25519 signing seedとしてエンコードします。
+    /// 認証鍵はここから再導出できます。
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.seed
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SuteraKeypairError> {
+        let seed: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| SuteraKeypairError::InvalidLength(bytes.len()))?;
+
+        Ok(Self::from_seed(seed))
+    }
+
+    pub fn to_base58_string(&self) -> String {
+        bs58::encode(self.to_bytes()).into_string()
+    }
+
+    pub fn from_base58_string(value: &str) -> Result<Self, SuteraKeypairError> {
+        let bytes = bs58::decode(value).into_vec()?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Persist this keypair to `path` as JSON containing its base58 encoding.
+    /// このkeypairをbase58エンコードを含むJSONとして`path`に永続化します。
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<(), SuteraKeypairError> {
+        let payload = KeypairFilePayload {
+            keypair: self.to_base58_string(),
+        };
+        fs::write(path, serde_json::to_string_pretty(&payload)?)?;
+        Ok(())
+    }
+
+    /// Restore a keypair previously written with [`Self::write_to_file`].
+    /// [`Self::write_to_file`]で書き出されたkeypairを復元します。
+    pub fn read_from_file(path: impl AsRef<Path>) -> Result<Self, SuteraKeypairError> {
+        let payload: KeypairFilePayload = serde_json::from_str(&fs::read_to_string(path)?)?;
+        Self::from_base58_string(&payload.keypair)
+    }
+
+    /// Produce the [`SuteraIdentity`] matching this keypair's verifying key.
+    /// このkeypairの認証鍵に対応する[`SuteraIdentity`]を生成します。
+    pub fn to_identity(&self, kind: SuteraIdentityKind, display_name: Option<String>) -> SuteraIdentity {
+        SuteraIdentity {
+            kind,
+            display_name,
+            pub_signature: SuteraPublicKey::Ed25519(self.verifying_key.clone()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeypairFilePayload {
+    keypair: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn base58_round_trip() {
+        let keypair = Keypair::generate();
+        let encoded = keypair.to_base58_string();
+        let decoded = Keypair::from_base58_string(&encoded).unwrap();
+
+        assert_eq!(keypair.to_bytes(), decoded.to_bytes());
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let keypair = Keypair::generate();
+        let decoded = Keypair::from_bytes(&keypair.to_bytes()).unwrap();
+
+        assert_eq!(keypair.to_bytes(), decoded.to_bytes());
+    }
+
+    #[test]
+    fn from_bytes_rejects_wrong_length() {
+        assert!(matches!(
+            Keypair::from_bytes(&[0u8; 16]),
+            Err(SuteraKeypairError::InvalidLength(16))
+        ));
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let keypair = Keypair::generate();
+        let path = std::env::temp_dir().join(format!(
+            "sutera-keypair-test-{}.json",
+            keypair.to_base58_string()
+        ));
+
+        keypair.write_to_file(&path).unwrap();
+        let restored = Keypair::read_from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(keypair.to_bytes(), restored.to_bytes());
+    }
+
+    #[test]
+    fn to_identity_matches_verifying_key() {
+        let keypair = Keypair::generate();
+        let identity = keypair.to_identity(SuteraIdentityKind::User, Some("see2et".to_string()));
+
+        assert_eq!(
+            identity.pub_signature,
+            SuteraPublicKey::Ed25519(keypair.verifying_key().clone())
+        );
+    }
+}
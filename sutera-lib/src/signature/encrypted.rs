@@ -0,0 +1,278 @@
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Nonce,
+};
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use hkdf::Hkdf;
+use rand_core::{OsRng, RngCore};
+use ring_compat::signature::{ed25519, Signer, Verifier};
+use sha2::{Digest, Sha256, Sha512};
+use thiserror::Error;
+use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret as X25519StaticSecret};
+
+use crate::identity::keypair::Keypair;
+
+use super::identity::SuteraIdentity;
+
+/// An error that occurs when sealing or opening a [`SuteraEncryptedMessage`].
+/// [`SuteraEncryptedMessage`]の封印・開封の際に起きるエラー。
+#[derive(Debug, Error)]
+pub enum SuteraEncryptionError {
+    #[error("the signing key does not match the author's verifying key")]
+    SigningKeyMismatch,
+    #[error("X25519 key agreement currently only supports ed25519 identities")]
+    UnsupportedAlgorithm,
+    #[error("the peer's ed25519 public key is not a valid curve point")]
+    InvalidPeerKey,
+    #[error("key agreement produced a low-order (all-zero) shared secret")]
+    LowOrderSharedSecret,
+    #[error("encryption failed")]
+    EncryptionFailed,
+    #[error("decryption failed: the message is corrupt, was not addressed to this recipient, or the authentication tag did not match")]
+    DecryptionFailed,
+}
+
+/// Convert an ed25519 signing seed to the X25519 scalar used for Diffie-Hellman,
+/// by hashing it with SHA-512 and clamping the low half per RFC 7748 §5, the
+/// same transform general-purpose key crates use to reuse an ed25519 key for
+/// ECDH.
+///
+/// This takes the raw seed rather than a `ring_compat::ed25519::SigningKey`
+/// because that type never hands its seed back out (it only exposes
+/// `verifying_key()`/`sign()`), so [`Keypair`], which keeps the seed it was
+/// built from, is the only place this scalar can legitimately come from.
+/// ed25519署名のseedをSHA-512でハッシュ化し、RFC 7748 §5に従って下位32byteをクランプすることで、
+/// Diffie-Hellmanに用いるX25519スカラーに変換します。
+///
+/// `ring_compat::ed25519::SigningKey`ではなく生のseedを受け取るのは、
+/// この型がseedを外部に公開しない(`verifying_key()`/`sign()`のみを提供する)ためです。
+/// そのため、構築時のseedを保持している[`Keypair`]だけが、このスカラーの正当な導出元となります。
+fn ed25519_seed_to_x25519(seed: &[u8; 32]) -> X25519StaticSecret {
+    let digest = Sha512::digest(seed);
+    let mut scalar_bytes = [0u8; 32];
+    scalar_bytes.copy_from_slice(&digest[..32]);
+    X25519StaticSecret::from(scalar_bytes)
+}
+
+/// Convert an ed25519 verifying key to its X25519 (Montgomery form) counterpart,
+/// rejecting keys that do not decompress to a valid Edwards point.
+/// ed25519認証鍵をX25519(Montgomery form)の鍵へ変換します。
+/// 有効なEdwards pointへデコンプレスできない鍵は拒否されます。
+fn ed25519_verifying_to_x25519(
+    verifying: &ed25519::VerifyingKey,
+) -> Result<X25519PublicKey, SuteraEncryptionError> {
+    let bytes: [u8; 32] = verifying
+        .as_ref()
+        .try_into()
+        .expect("ed25519 verifying keys are 32 bytes");
+    let point = CompressedEdwardsY(bytes)
+        .decompress()
+        .ok_or(SuteraEncryptionError::InvalidPeerKey)?;
+    Ok(X25519PublicKey::from(point.to_montgomery().to_bytes()))
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |acc, byte| {
+            acc + &format!("{:02x}", byte)
+        })
+}
+
+/// Derive the symmetric AEAD key from an X25519 shared secret via HKDF-SHA256,
+/// binding it to both peers' identity material as context so that a shared
+/// secret cannot be replayed against a different pair of identities.
+/// X25519の共有鍵からHKDF-SHA256を用いて対称鍵を導出します。
+/// 共有鍵が異なるidentityの組み合わせに対して再利用されないよう、
+/// 両者のidentity情報をcontextとして紐付けます。
+fn derive_symmetric_key(
+    shared_secret: &[u8],
+    author: &SuteraIdentity,
+    recipient_pubkey_hex: &str,
+) -> [u8; 32] {
+    let author_identity_string: String = author.clone().into();
+    let info = format!("{}|{}", author_identity_string, recipient_pubkey_hex);
+
+    let hk = Hkdf::<Sha256>::new(None, shared_secret);
+    let mut key = [0u8; 32];
+    hk.expand(info.as_bytes(), &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+/// An encrypted, authenticated message exchanged between two [`SuteraIdentity`]s.
+/// The sender's ed25519 key is reused for X25519 key agreement to derive a
+/// symmetric key, and the ciphertext is signed so recipients can both
+/// authenticate and decrypt it.
+/// 2つの[`SuteraIdentity`]間でやり取りされる, 暗号化され認証されたメッセージ。
+/// 送信者のed25519鍵はX25519鍵交換に再利用され、対称鍵の導出に用いられます。
+/// また、暗号文には署名が付与されており、受信者は認証と復号の両方を行うことができます。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SuteraEncryptedMessage {
+    pub author: SuteraIdentity,
+    pub ciphertext: Vec<u8>,
+    pub nonce: [u8; 12],
+    pub signature: ed25519::Signature,
+}
+
+impl SuteraEncryptedMessage {
+    /// Seal `plaintext` for `recipient`, authenticated as `author`.
+    /// `recipient`向けに`plaintext`を封印し、`author`として認証します。
+    ///
+    /// ## Returns / 戻り値
+    /// if the signing key does not match the author's verifying key, or if the
+    /// recipient's public key is invalid or low-order, return `Err`.
+    /// 署名鍵が署名者の認証鍵と合致しない場合、または受信者の公開鍵が不正か低位数の場合、
+    /// `Err`が返却されます。
+    pub fn seal(
+        author: SuteraIdentity,
+        author_signing: &Keypair,
+        recipient: &SuteraIdentity,
+        plaintext: &str,
+    ) -> Result<Self, SuteraEncryptionError> {
+        let author_key = author
+            .pub_signature
+            .as_ed25519()
+            .ok_or(SuteraEncryptionError::UnsupportedAlgorithm)?;
+        if author_signing.verifying_key() != author_key {
+            return Err(SuteraEncryptionError::SigningKeyMismatch);
+        }
+
+        let recipient_key = recipient
+            .pub_signature
+            .as_ed25519()
+            .ok_or(SuteraEncryptionError::UnsupportedAlgorithm)?;
+
+        let sender_secret = ed25519_seed_to_x25519(author_signing.seed());
+        let recipient_public = ed25519_verifying_to_x25519(recipient_key)?;
+
+        let shared_secret = sender_secret.diffie_hellman(&recipient_public);
+        if shared_secret.as_bytes().iter().all(|byte| *byte == 0) {
+            return Err(SuteraEncryptionError::LowOrderSharedSecret);
+        }
+
+        let key = derive_symmetric_key(
+            shared_secret.as_bytes(),
+            &author,
+            &hex_encode(recipient_key.as_ref()),
+        );
+
+        let mut nonce_bytes = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce_bytes);
+
+        let cipher = ChaCha20Poly1305::new(&key.into());
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce_bytes), plaintext.as_bytes())
+            .map_err(|_| SuteraEncryptionError::EncryptionFailed)?;
+
+        let signature = author_signing.signing_key().sign(&ciphertext);
+
+        Ok(SuteraEncryptedMessage {
+            author,
+            ciphertext,
+            nonce: nonce_bytes,
+            signature,
+        })
+    }
+
+    /// Open a message sealed by [`Self::seal`] using the recipient's signing key.
+    /// [`Self::seal`]で封印されたメッセージを、受信者の署名鍵を用いて開封します。
+    ///
+    /// ## Returns / 戻り値
+    /// if the ciphertext's signature is invalid, the peer's key is unusable, or
+    /// decryption fails (e.g. the message was not addressed to this recipient),
+    /// return `Err`.
+    /// 暗号文の署名が不正な場合、相手の鍵が利用不能な場合、あるいは復号に失敗した場合
+    /// (例えばこのメッセージが本来の受信者宛でなかった場合)、`Err`が返却されます。
+    pub fn open(&self, recipient_signing: &Keypair) -> Result<String, SuteraEncryptionError> {
+        let author_key = self
+            .author
+            .pub_signature
+            .as_ed25519()
+            .ok_or(SuteraEncryptionError::UnsupportedAlgorithm)?;
+
+        if author_key
+            .verify(&self.ciphertext, &self.signature)
+            .is_err()
+        {
+            return Err(SuteraEncryptionError::DecryptionFailed);
+        }
+
+        let recipient_secret = ed25519_seed_to_x25519(recipient_signing.seed());
+        let sender_public = ed25519_verifying_to_x25519(author_key)?;
+
+        let shared_secret = recipient_secret.diffie_hellman(&sender_public);
+        if shared_secret.as_bytes().iter().all(|byte| *byte == 0) {
+            return Err(SuteraEncryptionError::LowOrderSharedSecret);
+        }
+
+        let recipient_pubkey_hex = hex_encode(recipient_signing.verifying_key().as_ref());
+        let key = derive_symmetric_key(shared_secret.as_bytes(), &self.author, &recipient_pubkey_hex);
+
+        let cipher = ChaCha20Poly1305::new(&key.into());
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&self.nonce), self.ciphertext.as_slice())
+            .map_err(|_| SuteraEncryptionError::DecryptionFailed)?;
+
+        String::from_utf8(plaintext).map_err(|_| SuteraEncryptionError::DecryptionFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature::identity::SuteraIdentityKind;
+    use pretty_assertions::assert_eq;
+
+    fn test_identity() -> (SuteraIdentity, Keypair) {
+        let keypair = Keypair::generate();
+        let identity = keypair.to_identity(SuteraIdentityKind::User, Some("see2et".to_string()));
+        (identity, keypair)
+    }
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let (author, author_signing) = test_identity();
+        let (recipient, recipient_signing) = test_identity();
+
+        let sealed = SuteraEncryptedMessage::seal(
+            author,
+            &author_signing,
+            &recipient,
+            "Hello, Sutera!",
+        )
+        .unwrap();
+
+        let opened = sealed.open(&recipient_signing).unwrap();
+        assert_eq!(opened, "Hello, Sutera!");
+    }
+
+    #[test]
+    fn open_fails_for_wrong_recipient() {
+        let (author, author_signing) = test_identity();
+        let (recipient, _) = test_identity();
+        let (_, someone_elses_signing) = test_identity();
+
+        let sealed = SuteraEncryptedMessage::seal(
+            author,
+            &author_signing,
+            &recipient,
+            "Hello, Sutera!",
+        )
+        .unwrap();
+
+        assert!(sealed.open(&someone_elses_signing).is_err());
+    }
+
+    #[test]
+    fn seal_rejects_signing_key_mismatch() {
+        let (author, _) = test_identity();
+        let (recipient, _) = test_identity();
+        let (_, impostor_signing) = test_identity();
+
+        assert!(matches!(
+            SuteraEncryptedMessage::seal(author, &impostor_signing, &recipient, "hi"),
+            Err(SuteraEncryptionError::SigningKeyMismatch)
+        ));
+    }
+}
@@ -0,0 +1,326 @@
+use ring_compat::signature::{ed25519, Signer, Verifier};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use super::identity::SuteraIdentity;
+
+/// The content-addressed identifier of a [`SuteraFeedMessage`].
+/// It is the SHA-256 digest of the message's canonicalized fields, rendered as a
+/// lowercase hexadecimal string.
+/// [`SuteraFeedMessage`]のコンテンツアドレス型の識別子。
+/// メッセージの正規化されたフィールドのSHA-256ダイジェストを、小文字の16進数文字列として表現したものです。
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MessageId(String);
+
+impl MessageId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for MessageId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A single entry in an append-only, per-identity message log, modeled after
+/// Secure Scuttlebutt's feed format.
+/// Each message references the id of the message preceding it, so a feed can be
+/// replayed and its entire history can be verified for tampering.
+/// 追記専用のidentityごとのメッセージログにおける1エントリ。Secure Scuttlebuttのfeed形式を参考にしています。
+/// 各メッセージは直前のメッセージのidを参照するため、feedを再生しながら改竄がないか履歴全体を検証できます。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SuteraFeedMessage {
+    pub author: SuteraIdentity,
+    pub content: String,
+    pub sequence: u64,
+    pub timestamp: u64,
+    pub previous: Option<MessageId>,
+    pub signature: ed25519::Signature,
+}
+
+/// An error that occurs when appending a new message to a feed.
+/// feedに新しいメッセージを追記する際に起きるエラー。
+#[derive(Debug, Error)]
+pub enum SuteraFeedMessageError {
+    #[error("the signing key does not match the author's verifying key")]
+    SigningKeyMismatch,
+    #[error("feed messages currently only support ed25519 identities")]
+    UnsupportedAlgorithm,
+}
+
+/// An error that occurs when verifying the integrity of a feed.
+/// The index refers to the position of the first offending message in the slice
+/// that was passed to [`verify_chain`].
+/// feedの整合性を検証する際に起きるエラー。
+/// indexは[`verify_chain`]に渡されたスライスの中で、最初に問題が見つかったメッセージの位置を示します。
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SuteraFeedChainError {
+    #[error("message at index {0} has an invalid signature")]
+    InvalidSignature(usize),
+    #[error("message at index {index} has sequence {found}, expected {expected}")]
+    SequenceMismatch {
+        index: usize,
+        found: u64,
+        expected: u64,
+    },
+    #[error("message at index {0} does not reference the id of the preceding message")]
+    PreviousMismatch(usize),
+    #[error("message at index {0} has a different author than the rest of the chain")]
+    AuthorMismatch(usize),
+}
+
+impl SuteraFeedMessage {
+    /// Build the canonical byte representation of a message's fields, in a
+    /// stable key order, so that hashing and signing are reproducible.
+    /// メッセージのフィールドを安定したキー順序で正規化したバイト列を構築します。
+    /// これによりハッシュ化と署名が再現可能になります。
+    fn canonical_bytes(
+        author: &SuteraIdentity,
+        previous: &Option<MessageId>,
+        sequence: u64,
+        timestamp: u64,
+        content: &str,
+    ) -> Vec<u8> {
+        let author_str: String = author.clone().into();
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(author_str.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(author_str.as_bytes());
+        match previous {
+            Some(id) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&(id.as_str().len() as u64).to_be_bytes());
+                bytes.extend_from_slice(id.as_str().as_bytes());
+            }
+            None => bytes.push(0),
+        }
+        bytes.extend_from_slice(&sequence.to_be_bytes());
+        bytes.extend_from_slice(&timestamp.to_be_bytes());
+        bytes.extend_from_slice(&(content.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(content.as_bytes());
+        bytes
+    }
+
+    /// Compute the [`MessageId`] of this message, independent of its signature.
+    /// このメッセージの[`MessageId`]を署名とは独立に計算します。
+    pub fn id(&self) -> MessageId {
+        let bytes = Self::canonical_bytes(
+            &self.author,
+            &self.previous,
+            self.sequence,
+            self.timestamp,
+            &self.content,
+        );
+        MessageId(
+            Sha256::digest(&bytes)
+                .iter()
+                .fold(String::with_capacity(64), |acc, byte| {
+                    acc + &format!("{:02x}", byte)
+                }),
+        )
+    }
+
+    /// Append a new message to the end of a feed.
+    /// `prev` is the previous message in the feed, or `None` if this is the
+    /// first message. The sequence number and `previous` link are derived from
+    /// it automatically.
+    /// feedの末尾に新しいメッセージを追記します。
+    /// `prev`はfeed内の直前のメッセージであり、これが最初のメッセージである場合は`None`です。
+    /// sequence番号と`previous`リンクはここから自動的に導出されます。
+    ///
+    /// ## Returns / 戻り値
+    /// if the signing key does not match the author's verifying key, return `Err`.
+    /// 署名鍵が署名者の認証鍵と合致しない場合、`Err`が返却されます。
+    pub fn append(
+        prev: Option<&SuteraFeedMessage>,
+        author: SuteraIdentity,
+        content: String,
+        timestamp: u64,
+        signer: &ed25519::SigningKey,
+    ) -> Result<Self, SuteraFeedMessageError> {
+        let verifying_key = author
+            .pub_signature
+            .as_ed25519()
+            .ok_or(SuteraFeedMessageError::UnsupportedAlgorithm)?;
+        if signer.verifying_key() != *verifying_key {
+            return Err(SuteraFeedMessageError::SigningKeyMismatch);
+        }
+
+        let sequence = prev.map_or(0, |message| message.sequence + 1);
+        let previous = prev.map(|message| message.id());
+
+        let bytes = Self::canonical_bytes(&author, &previous, sequence, timestamp, &content);
+        let signature = signer.sign(&bytes);
+
+        Ok(SuteraFeedMessage {
+            author,
+            content,
+            sequence,
+            timestamp,
+            previous,
+            signature,
+        })
+    }
+
+    /// Check if the signature over this message's canonicalized fields is valid.
+    /// このメッセージの正規化されたフィールドに対する署名が有効かどうかを確認します。
+    pub fn verify(&self) -> bool {
+        let bytes = Self::canonical_bytes(
+            &self.author,
+            &self.previous,
+            self.sequence,
+            self.timestamp,
+            &self.content,
+        );
+        match self.author.pub_signature.as_ed25519() {
+            Some(key) => key.verify(&bytes, &self.signature).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// Verify that a slice of messages forms a valid, tamper-evident feed: every
+/// signature checks out, `sequence` increments by exactly one, every `previous`
+/// equals the preceding message's computed id, and the author never changes.
+/// メッセージのスライスが改竄検知可能な正当なfeedを構成しているか検証します。
+/// 全ての署名が有効であり、`sequence`がちょうど1ずつ増加し、全ての`previous`が
+/// 直前のメッセージの計算済みidと一致し、authorが一貫していることを確認します。
+///
+/// ## Returns / 戻り値
+/// the index and reason of the first violation found, if any.
+/// 違反が見つかった場合、その最初のindexと理由が返却されます。
+pub fn verify_chain(messages: &[SuteraFeedMessage]) -> Result<(), SuteraFeedChainError> {
+    for (index, message) in messages.iter().enumerate() {
+        if index > 0 {
+            let previous = &messages[index - 1];
+
+            if message.author != previous.author {
+                return Err(SuteraFeedChainError::AuthorMismatch(index));
+            }
+
+            let expected_sequence = previous.sequence + 1;
+            if message.sequence != expected_sequence {
+                return Err(SuteraFeedChainError::SequenceMismatch {
+                    index,
+                    found: message.sequence,
+                    expected: expected_sequence,
+                });
+            }
+
+            if message.previous.as_ref() != Some(&previous.id()) {
+                return Err(SuteraFeedChainError::PreviousMismatch(index));
+            }
+        }
+
+        // Checked last: tampering with any field above (sequence, author,
+        // previous) also invalidates the signature, since they are all part
+        // of `canonical_bytes`. Checking the more specific structural
+        // violations first gives callers a precise reason instead of a
+        // blanket `InvalidSignature`.
+        if !message.verify() {
+            return Err(SuteraFeedChainError::InvalidSignature(index));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature::identity::{SuteraIdentityKind, SuteraPublicKey};
+    use pretty_assertions::assert_eq;
+    use rand_core::{OsRng, RngCore};
+
+    fn test_author() -> (SuteraIdentity, ed25519::SigningKey) {
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        let secret = ed25519::SigningKey::from_seed(&seed).unwrap();
+
+        let identity = SuteraIdentity {
+            kind: SuteraIdentityKind::User,
+            display_name: Some("see2et".to_string()),
+            pub_signature: SuteraPublicKey::Ed25519(secret.verifying_key()),
+        };
+
+        (identity, secret)
+    }
+
+    #[test]
+    fn append_and_verify_chain() {
+        let (author, secret) = test_author();
+
+        let first =
+            SuteraFeedMessage::append(None, author.clone(), "hello".to_string(), 0, &secret)
+                .unwrap();
+        assert_eq!(first.sequence, 0);
+        assert_eq!(first.previous, None);
+
+        let second = SuteraFeedMessage::append(
+            Some(&first),
+            author.clone(),
+            "world".to_string(),
+            1,
+            &secret,
+        )
+        .unwrap();
+        assert_eq!(second.sequence, 1);
+        assert_eq!(second.previous, Some(first.id()));
+
+        assert!(verify_chain(&[first, second]).is_ok());
+    }
+
+    #[test]
+    fn append_rejects_signing_key_mismatch() {
+        let (author, _) = test_author();
+        let mut other_seed = [0u8; 32];
+        OsRng.fill_bytes(&mut other_seed);
+        let impostor_secret = ed25519::SigningKey::from_seed(&other_seed).unwrap();
+
+        assert!(
+            SuteraFeedMessage::append(None, author, "hi".to_string(), 0, &impostor_secret)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn verify_chain_rejects_tampered_content() {
+        let (author, secret) = test_author();
+        let mut first =
+            SuteraFeedMessage::append(None, author, "hello".to_string(), 0, &secret).unwrap();
+        first.content = "goodbye".to_string();
+
+        assert_eq!(
+            verify_chain(&[first]),
+            Err(SuteraFeedChainError::InvalidSignature(0))
+        );
+    }
+
+    #[test]
+    fn verify_chain_rejects_sequence_gap() {
+        let (author, secret) = test_author();
+        let first = SuteraFeedMessage::append(
+            None,
+            author.clone(),
+            "hello".to_string(),
+            0,
+            &secret,
+        )
+        .unwrap();
+        let mut second =
+            SuteraFeedMessage::append(Some(&first), author, "world".to_string(), 1, &secret)
+                .unwrap();
+        second.sequence = 5;
+
+        assert_eq!(
+            verify_chain(&[first, second]),
+            Err(SuteraFeedChainError::SequenceMismatch {
+                index: 1,
+                found: 5,
+                expected: 1,
+            })
+        );
+    }
+}
@@ -1,8 +1,141 @@
 use std::str::FromStr;
 
+use k256::ecdsa::VerifyingKey as Secp256k1VerifyingKey;
 use ring_compat::signature::ed25519;
 use thiserror::Error;
 
+/// The multicodec tag for an ed25519 public key (`0xed`).
+/// ed25519公開鍵のmulticodecタグ(`0xed`)。
+const MULTICODEC_ED25519_PUB: u64 = 0xed;
+/// The multicodec tag for a secp256k1 public key (`0xe7`).
+/// secp256k1公開鍵のmulticodecタグ(`0xe7`)。
+const MULTICODEC_SECP256K1_PUB: u64 = 0xe7;
+
+/// Encode `value` as an unsigned varint (LEB128), appending it to `out`.
+/// `value`をunsigned varint(LEB128)として`out`に追記します。
+fn varint_encode(mut value: u64, out: &mut Vec<u8>) {
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value != 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+/// Decode an unsigned varint (LEB128) from the start of `bytes`, returning the
+/// value and the number of bytes it occupied.
+/// `bytes`の先頭からunsigned varint(LEB128)をデコードし、値と消費したbyte数を返します。
+fn varint_decode(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value: u64 = 0;
+    let mut shift = 0;
+    for (index, &byte) in bytes.iter().enumerate() {
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some((value, index + 1));
+        }
+        shift += 7;
+    }
+    None
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, SuteraIdentityStringParseError> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(SuteraIdentityStringParseError::InvalidFormat);
+    }
+    hex.as_bytes()
+        .chunks(2)
+        .map(|chunk| u8::from_str_radix(std::str::from_utf8(chunk).unwrap(), 16))
+        .collect::<Result<Vec<u8>, std::num::ParseIntError>>()
+        .or(Err(SuteraIdentityStringParseError::InvalidFormat))
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |acc, byte| {
+            acc + &format!("{:02x}", byte)
+        })
+}
+
+/// The algorithm-specific public key backing a [`SuteraIdentity`].
+/// In `sutera-identity-v2` strings, the key is encoded behind a short
+/// self-describing multicodec-style prefix (a varint tag, e.g. `0xed` for
+/// ed25519 and `0xe7` for secp256k1) so that new signature algorithms can be
+/// adopted without breaking identities that only use ed25519.
+/// [`SuteraIdentity`]が保持するアルゴリズム固有の公開鍵。
+/// `sutera-identity-v2`文字列では、鍵は短い自己記述的なmulticodec形式のprefix
+/// (varintタグ, 例: ed25519なら`0xed`, secp256k1なら`0xe7`)の後ろにエンコードされており、
+/// ed25519のみを用いる既存のidentityを壊すことなく新しい署名アルゴリズムを採用できます。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SuteraPublicKey {
+    Ed25519(ed25519::VerifyingKey),
+    Secp256k1(Secp256k1VerifyingKey),
+}
+
+impl SuteraPublicKey {
+    /// Return the ed25519 verifying key, if this is an [`SuteraPublicKey::Ed25519`].
+    /// Most of this crate's signing machinery only supports ed25519 today.
+    /// この鍵が[`SuteraPublicKey::Ed25519`]である場合、そのed25519認証鍵を返します。
+    /// このクレートの署名処理の大半は、現時点ではed25519のみをサポートしています。
+    pub fn as_ed25519(&self) -> Option<&ed25519::VerifyingKey> {
+        match self {
+            SuteraPublicKey::Ed25519(key) => Some(key),
+            SuteraPublicKey::Secp256k1(_) => None,
+        }
+    }
+
+    fn multicodec_tag(&self) -> u64 {
+        match self {
+            SuteraPublicKey::Ed25519(_) => MULTICODEC_ED25519_PUB,
+            SuteraPublicKey::Secp256k1(_) => MULTICODEC_SECP256K1_PUB,
+        }
+    }
+
+    fn key_bytes(&self) -> Vec<u8> {
+        match self {
+            SuteraPublicKey::Ed25519(key) => key.as_ref().to_vec(),
+            SuteraPublicKey::Secp256k1(key) => key.to_encoded_point(true).as_bytes().to_vec(),
+        }
+    }
+
+    /// Encode as `{multicodec-tag-varint}{key-bytes}`, hex-encoded.
+    /// `{multicodecタグのvarint}{鍵のbyte列}`をhexエンコードしたものを返します。
+    fn to_prefixed_hex(&self) -> String {
+        let mut bytes = Vec::new();
+        varint_encode(self.multicodec_tag(), &mut bytes);
+        bytes.extend(self.key_bytes());
+        encode_hex(&bytes)
+    }
+
+    fn from_prefixed_hex(hex: &str) -> Result<Self, SuteraIdentityStringParseError> {
+        let bytes = decode_hex(hex)?;
+        let (tag, tag_len) =
+            varint_decode(&bytes).ok_or(SuteraIdentityStringParseError::InvalidFormat)?;
+        let key_bytes = &bytes[tag_len..];
+
+        match tag {
+            MULTICODEC_ED25519_PUB => {
+                let key = ed25519::VerifyingKey::new(key_bytes)
+                    .or(Err(SuteraIdentityStringParseError::InvalidFormat))?;
+                Ok(SuteraPublicKey::Ed25519(key))
+            }
+            MULTICODEC_SECP256K1_PUB => {
+                let key = Secp256k1VerifyingKey::from_sec1_bytes(key_bytes)
+                    .or(Err(SuteraIdentityStringParseError::InvalidFormat))?;
+                Ok(SuteraPublicKey::Secp256k1(key))
+            }
+            unsupported => Err(SuteraIdentityStringParseError::UnsupportedAlgorithm(
+                unsupported,
+            )),
+        }
+    }
+}
+
 /// An error that occurs when parsing a Sutera identity string.
 /// Sutera-identity-stringをパースする際に起きるエラー。
 #[derive(Debug, Error, PartialEq, Eq)]
@@ -13,6 +146,8 @@ pub enum SuteraIdentityStringParseError {
     VersionMismatch(String),
     #[error("invalid identity string, kind {0} is not supported")]
     UnsupportedKind(String),
+    #[error("invalid identity string, multicodec algorithm tag {0:#x} is not supported")]
+    UnsupportedAlgorithm(u64),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, strum::EnumString, strum::AsRefStr)]
@@ -29,47 +164,44 @@ pub struct SuteraIdentity {
     /// Suteraネットワークで扱うオブジェクトの種類 (例: ユーザー、サーバー、ワールドなど)
     pub kind: SuteraIdentityKind,
 
-    /// The display name of the identity.  
-    /// This is only designated for human-readable purposes and plays no role in authentication.  
+    /// The display name of the identity.
+    /// This is only designated for human-readable purposes and plays no role in authentication.
     /// display_name can only contain alphanumeric characters (0-9, a-z)
     /// identityの表示名。
     /// これは人間の理解を促進するためだけに定義されており、認証プロセスにおいて何の役目も果たしません。
     /// 表示名には英数字(0-9, a-z)のみを利用することができます。
     pub display_name: Option<String>,
 
-    /// The ed25519 public key of the identity.  
+    /// The public key of the identity, tagged with its algorithm.
     /// This is used to verify the signature of the identity.
-    /// identityのed25519公開鍵です。
+    /// identityの公開鍵です。アルゴリズムのタグが付与されています。
     /// identityの署名を検証されるために使用されます。
-    pub pub_signature: ed25519::VerifyingKey,
+    pub pub_signature: SuteraPublicKey,
 }
 
-/// Convert SuteraIdentity to String.  
-/// The format is `{type}@{display_name}.sutera-identity-v1.{pub_signature}`.  
-/// Because pub_signature is 32byte, so the part `{pub_signature}` is 64 letters hexadecimal string.  
+/// Convert SuteraIdentity to String.
+/// The format is `{type}@{display_name}.sutera-identity-v2.{multicodec-tag}{pub_signature}`.
+/// `{multicodec-tag}{pub_signature}` is the hex encoding of a varint algorithm
+/// tag followed by the raw public key bytes, so the key length depends on the algorithm.
 /// SuteraIdentityを文字列に変換します。
-/// 形式は `{type}@{display_name}.sutera-identity-v1.{pub_signature}` です。
-/// TODO: ここよく分からない！
+/// 形式は `{type}@{display_name}.sutera-identity-v2.{multicodecタグ}{pub_signature}` です。
+/// `{multicodecタグ}{pub_signature}` はvarintのアルゴリズムタグと生の公開鍵byte列をhexエンコードしたものであり、
+/// 鍵の長さはアルゴリズムによって変わります。
 ///
 /// ## Example
 /// ```no_test
-/// user.sutera-identity-v1.fffffff.....
-/// user@alice.sutera-identity-v1.fffffff.....
+/// user.sutera-identity-v2.ed01fffffff.....
+/// user@alice.sutera-identity-v2.ed01fffffff.....
 /// ```
 impl From<SuteraIdentity> for String {
     fn from(identity: SuteraIdentity) -> String {
         format!(
-            "{}.sutera-identity-v1.{}",
+            "{}.sutera-identity-v2.{}",
             match identity.display_name {
                 Some(display_name) => format!("{}@{}", identity.kind.as_ref(), display_name),
                 None => identity.kind.as_ref().to_string(),
             },
-            identity
-                .pub_signature
-                .0
-                .iter()
-                .fold(String::with_capacity(64), |acc, byte| acc
-                    + &format!("{:02x}", byte))
+            identity.pub_signature.to_prefixed_hex()
         )
     }
 }
@@ -89,14 +221,12 @@ impl TryFrom<String> for SuteraIdentity {
             return Err(SuteraIdentityStringParseError::InvalidFormat);
         }
 
-        if version != "sutera-identity-v1" {
+        if version != "sutera-identity-v1" && version != "sutera-identity-v2" {
             return Err(SuteraIdentityStringParseError::VersionMismatch(version));
         }
 
-        if pub_key.len() != 64 {
-            return Err(SuteraIdentityStringParseError::InvalidFormat);
-        }
-
+        // Parsed before the public key so that an unsupported kind is always
+        // reported as `UnsupportedKind`, even when the key part is also malformed.
         let (kind, display_name) = match kind.find('@') {
             Some(index) => (
                 SuteraIdentityKind::from_str(&kind[..index]).map_err(|_| {
@@ -112,17 +242,26 @@ impl TryFrom<String> for SuteraIdentity {
             ),
         };
 
-        let pub_key_bytes = parts[2]
-            .as_bytes()
-            .chunks(2)
-            .map(|chunk| u8::from_str_radix(std::str::from_utf8(chunk).unwrap(), 16))
-            .collect::<Result<Vec<u8>, std::num::ParseIntError>>()
-            .or(Err(SuteraIdentityStringParseError::InvalidFormat))?;
+        let pub_signature = match version.as_str() {
+            // Legacy format: a bare 64-char hex ed25519 key, with no algorithm prefix.
+            // レガシー形式: アルゴリズムのprefixを持たない, 64文字の生のed25519鍵。
+            "sutera-identity-v1" => {
+                if pub_key.len() != 64 {
+                    return Err(SuteraIdentityStringParseError::InvalidFormat);
+                }
+                let key_bytes = decode_hex(pub_key)?;
+                let key = ed25519::VerifyingKey::new(&key_bytes)
+                    .or(Err(SuteraIdentityStringParseError::InvalidFormat))?;
+                SuteraPublicKey::Ed25519(key)
+            }
+            "sutera-identity-v2" => SuteraPublicKey::from_prefixed_hex(pub_key)?,
+            _ => unreachable!(),
+        };
 
         Ok(SuteraIdentity {
             kind,
             display_name,
-            pub_signature: ed25519::VerifyingKey(pub_key_bytes.try_into().unwrap()),
+            pub_signature,
         })
     }
 }
@@ -139,13 +278,13 @@ mod tests {
         let identity = SuteraIdentity {
             kind: SuteraIdentityKind::User,
             display_name: Some("see2et".to_string()),
-            pub_signature: ed25519::VerifyingKey([0; 32]),
+            pub_signature: SuteraPublicKey::Ed25519(ed25519::VerifyingKey::new(&[0u8; 32]).unwrap()),
         };
 
         let identity_str: String = identity.clone().into();
         assert_eq!(
             identity_str,
-            "user@see2et.sutera-identity-v1.0000000000000000000000000000000000000000000000000000000000000000"
+            "user@see2et.sutera-identity-v2.ed010000000000000000000000000000000000000000000000000000000000000000"
         );
 
         // 変換した文字列をSuteraIdentityに戻し, オリジナルのSuteraIdentityと一致するか検証
@@ -159,13 +298,13 @@ mod tests {
         let identity = SuteraIdentity {
             kind: SuteraIdentityKind::User,
             display_name: None,
-            pub_signature: ed25519::VerifyingKey([0; 32]),
+            pub_signature: SuteraPublicKey::Ed25519(ed25519::VerifyingKey::new(&[0u8; 32]).unwrap()),
         };
 
         let identity_str: String = identity.clone().into();
         assert_eq!(
             identity_str,
-            "user.sutera-identity-v1.0000000000000000000000000000000000000000000000000000000000000000"
+            "user.sutera-identity-v2.ed010000000000000000000000000000000000000000000000000000000000000000"
         );
 
         // 変換した文字列をSuteraIdentityに戻し, オリジナルのSuteraIdentityと一致するか検証
@@ -173,14 +312,26 @@ mod tests {
         assert_eq!(identity, parsed_identity);
     }
 
+    #[test]
+    fn sutera_identity_string_legacy_v1_still_parses() {
+        // sutera-identity-v1 (prefixなしのed25519鍵) が引き続きパースできることを確認
+        let legacy_str =
+            "user@see2et.sutera-identity-v1.0000000000000000000000000000000000000000000000000000000000000000";
+        let parsed_identity: SuteraIdentity = legacy_str.to_string().try_into().unwrap();
+        assert_eq!(
+            parsed_identity.pub_signature,
+            SuteraPublicKey::Ed25519(ed25519::VerifyingKey::new(&[0u8; 32]).unwrap())
+        );
+    }
+
     #[test]
     fn sutera_identity_string_version_mismatch() {
         // バージョンが異なる文字列をSuteraIdentityに変換しようとした場合のエラーを検証
-        let invalid_identity_str = "see2et.sutera-identity-v2.xxx";
+        let invalid_identity_str = "see2et.sutera-identity-v3.xxx";
         assert_eq!(
             SuteraIdentity::try_from(invalid_identity_str.to_string()),
             Err(SuteraIdentityStringParseError::VersionMismatch(
-                "sutera-identity-v2".to_string()
+                "sutera-identity-v3".to_string()
             ))
         );
     }
@@ -231,4 +382,28 @@ mod tests {
             Err(SuteraIdentityStringParseError::InvalidFormat)
         );
     }
+
+    #[test]
+    fn secp256k1_public_key_round_trips_through_prefixed_hex() {
+        // secp256k1公開鍵がmulticodec prefix付きのhex表現を経て復元できることを確認
+        let signing_key = k256::ecdsa::SigningKey::from_bytes(&[1u8; 32].into()).unwrap();
+        let public_key = SuteraPublicKey::Secp256k1(*signing_key.verifying_key());
+
+        let hex = public_key.to_prefixed_hex();
+        assert!(hex.starts_with("e701"));
+
+        let parsed = SuteraPublicKey::from_prefixed_hex(&hex).unwrap();
+        assert_eq!(public_key, parsed);
+        assert!(public_key.as_ed25519().is_none());
+    }
+
+    #[test]
+    fn sutera_identity_string_unsupported_algorithm() {
+        // 未知のmulticodecタグを持つv2文字列はUnsupportedAlgorithmエラーになることを確認
+        let invalid_identity_str = "user.sutera-identity-v2.ff0100";
+        assert_eq!(
+            SuteraIdentity::try_from(invalid_identity_str.to_string()),
+            Err(SuteraIdentityStringParseError::UnsupportedAlgorithm(0xff))
+        );
+    }
 }
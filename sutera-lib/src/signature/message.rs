@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
-use super::identity::SuteraIdentity;
+use super::identity::{SuteraIdentity, SuteraIdentityKind, SuteraPublicKey};
+use crate::identity::keypair::Keypair;
 use ring_compat::signature::{ed25519, Signer, Verifier};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -20,6 +21,8 @@ pub struct SuteraSignedMessage {
 pub enum SuteraMessageSigningError {
     #[error("the signing key does not match the author's verifying key")]
     SigningKeyMismatch,
+    #[error("message signing currently only supports ed25519 identities")]
+    UnsupportedAlgorithm,
 }
 
 impl SuteraSignedMessage {
@@ -39,10 +42,13 @@ impl SuteraSignedMessage {
     pub fn new(
         author: SuteraIdentity,
         message: String,
-        signer: ed25519::SigningKey,
+        signer: &ed25519::SigningKey,
     ) -> Result<Self, SuteraMessageSigningError> {
-        let verifying_key = signer.verifying_key();
-        if verifying_key != author.pub_signature {
+        let verifying_key = author
+            .pub_signature
+            .as_ed25519()
+            .ok_or(SuteraMessageSigningError::UnsupportedAlgorithm)?;
+        if signer.verifying_key() != *verifying_key {
             return Err(SuteraMessageSigningError::SigningKeyMismatch);
         }
 
@@ -55,6 +61,18 @@ impl SuteraSignedMessage {
         })
     }
 
+    /// Sign a message using a [`Keypair`] directly, so callers never have to
+    /// manually match a signing key against its author's verifying key.
+    /// [`Keypair`]を直接用いてメッセージに署名します。
+    /// 呼び出し側が署名鍵と署名者の認証鍵を手動で一致させる必要がなくなります。
+    pub fn new_from_keypair(
+        author: SuteraIdentity,
+        message: String,
+        keypair: &Keypair,
+    ) -> Result<Self, SuteraMessageSigningError> {
+        Self::new(author, message, keypair.signing_key())
+    }
+
     /// Check if the signature is valid.
     /// 署名が有効かどうかを確認します。
     ///
@@ -70,10 +88,10 @@ impl SuteraSignedMessage {
     /// `true` if the signature is valid, otherwise `false`.
     /// 署名が有効な場合は`true`、そうでない場合は`false`を返します。
     pub fn verify(&self) -> bool {
-        self.author
-            .pub_signature
-            .verify(self.message.as_bytes(), &self.signature)
-            .is_ok()
+        match self.author.pub_signature.as_ed25519() {
+            Some(key) => key.verify(self.message.as_bytes(), &self.signature).is_ok(),
+            None => false,
+        }
     }
 }
 
@@ -116,9 +134,100 @@ struct SuteraSignedMessagePayload {
     pub signature: String,
 }
 
+/// An error that occurs when encoding or decoding a [`SuteraSignedMessage`] as CBOR.
+/// [`SuteraSignedMessage`]をCBORとしてエンコード・デコードする際に起きるエラー。
+#[derive(Debug, Error)]
+pub enum SuteraSignedMessageCborError {
+    #[error("CBOR wire format currently only supports ed25519 identities")]
+    UnsupportedAlgorithm,
+    #[error("failed to encode message as CBOR")]
+    Encode(#[from] ciborium::ser::Error<std::io::Error>),
+    #[error("failed to decode message as CBOR")]
+    Decode(#[from] ciborium::de::Error<std::io::Error>),
+    #[error("decoded identity kind is not supported")]
+    UnsupportedKind,
+    #[error("decoded public key or signature has an invalid length")]
+    InvalidLength,
+    #[error("decoded message's signature does not verify")]
+    InvalidSignature,
+}
+
+/// The CBOR wire representation of a [`SuteraSignedMessage`]: the author's
+/// kind and display name, the raw 32-byte ed25519 public key and the raw
+/// 64-byte signature, instead of the full identity string and the signature string.
+/// The key and signature are `Vec<u8>` fields, which serde encodes as CBOR
+/// arrays of integers (one or two bytes per element), not as CBOR byte strings.
+/// [`SuteraSignedMessage`]のCBORワイヤー表現。
+/// authorの種類と表示名, 生の32byte ed25519公開鍵と生の64byte署名を、identity文字列全体や署名文字列ではなく保持します。
+/// 鍵と署名は`Vec<u8>`のフィールドであり、serdeはこれらをCBORのbyte stringではなく
+/// 整数の配列(要素ごとに1または2byte)としてエンコードします。
+#[derive(Serialize, Deserialize)]
+struct SuteraSignedMessageCborPayload {
+    kind: String,
+    display_name: Option<String>,
+    pub_key: Vec<u8>,
+    message: String,
+    signature: Vec<u8>,
+}
+
+impl SuteraSignedMessage {
+    /// Encode this message as a compact CBOR byte string.
+    /// このメッセージをコンパクトなCBORのバイト列としてエンコードします。
+    pub fn to_cbor(&self) -> Result<Vec<u8>, SuteraSignedMessageCborError> {
+        let pub_key = self
+            .author
+            .pub_signature
+            .as_ed25519()
+            .ok_or(SuteraSignedMessageCborError::UnsupportedAlgorithm)?;
+
+        let payload = SuteraSignedMessageCborPayload {
+            kind: self.author.kind.as_ref().to_string(),
+            display_name: self.author.display_name.clone(),
+            pub_key: pub_key.as_ref().to_vec(),
+            message: self.message.clone(),
+            signature: self.signature.as_ref().to_vec(),
+        };
+
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&payload, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Decode a message previously encoded with [`Self::to_cbor`], verifying
+    /// its signature before returning it.
+    /// [`Self::to_cbor`]でエンコードされたメッセージをデコードし、返却前に署名を検証します。
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, SuteraSignedMessageCborError> {
+        let payload: SuteraSignedMessageCborPayload = ciborium::de::from_reader(bytes)?;
+
+        let kind = SuteraIdentityKind::from_str(&payload.kind)
+            .map_err(|_| SuteraSignedMessageCborError::UnsupportedKind)?;
+
+        let pub_key = ed25519::VerifyingKey::new(&payload.pub_key)
+            .map_err(|_| SuteraSignedMessageCborError::InvalidLength)?;
+        let signature = ed25519::Signature::from_bytes(&payload.signature)
+            .map_err(|_| SuteraSignedMessageCborError::InvalidLength)?;
+
+        let message = SuteraSignedMessage {
+            author: SuteraIdentity {
+                kind,
+                display_name: payload.display_name,
+                pub_signature: SuteraPublicKey::Ed25519(pub_key),
+            },
+            message: payload.message,
+            signature,
+        };
+
+        if !message.verify() {
+            return Err(SuteraSignedMessageCborError::InvalidSignature);
+        }
+
+        Ok(message)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::signature::identity::SuteraIdentityKind;
+    use crate::signature::identity::{SuteraIdentityKind, SuteraPublicKey};
 
     use super::*;
     use pretty_assertions::assert_eq;
@@ -128,18 +237,18 @@ mod tests {
         // ランダムな秘密鍵を生成
         let mut ed25519_seed = [0u8; 32];
         OsRng.fill_bytes(&mut ed25519_seed);
-        let secret = ed25519::SigningKey::from_bytes(&ed25519_seed);
+        let secret = ed25519::SigningKey::from_seed(&ed25519_seed).unwrap();
 
         // 秘密鍵からSuteraIdentityを生成
         let identity = SuteraIdentity {
             kind: SuteraIdentityKind::User,
             display_name: Some("see2et".to_string()),
-            pub_signature: secret.verifying_key(),
+            pub_signature: SuteraPublicKey::Ed25519(secret.verifying_key()),
         };
 
         // 適当なStringをメッセージとして用意し,署名する
         let message = "Hello, Sutera!";
-        SuteraSignedMessage::new(identity, message.to_string(), secret).unwrap()
+        SuteraSignedMessage::new(identity, message.to_string(), &secret).unwrap()
     }
 
     #[test]
@@ -155,6 +264,22 @@ mod tests {
         assert!(!signed_message.verify());
     }
 
+    #[test]
+    fn sign_message_from_keypair() {
+        // Keypairから直接署名し, 対応するauthorが生成されることを確認
+        let keypair = crate::identity::keypair::Keypair::generate();
+        let identity = keypair.to_identity(SuteraIdentityKind::User, Some("see2et".to_string()));
+
+        let signed_message = SuteraSignedMessage::new_from_keypair(
+            identity,
+            "Hello, Sutera!".to_string(),
+            &keypair,
+        )
+        .unwrap();
+
+        assert!(signed_message.verify());
+    }
+
     #[test]
     fn signed_message_serializable() {
         // ランダムな秘密鍵で署名されたメッセージを生成
@@ -166,4 +291,30 @@ mod tests {
 
         assert_eq!(signed_message, deserialized);
     }
+
+    #[test]
+    fn cbor_round_trip_smaller_than_json() {
+        // ランダムな秘密鍵で署名されたメッセージを生成
+        let signed_message = test_signed_message();
+
+        let cbor = signed_message.to_cbor().unwrap();
+        let decoded = SuteraSignedMessage::from_cbor(&cbor).unwrap();
+        assert_eq!(signed_message, decoded);
+
+        // CBOR形式がJSON形式より小さくなることを確認
+        let json = serde_json::to_string(&signed_message).unwrap();
+        assert!(cbor.len() < json.len());
+    }
+
+    #[test]
+    fn cbor_from_truncated_buffer_is_a_clean_error() {
+        // ランダムな秘密鍵で署名されたメッセージを生成
+        let signed_message = test_signed_message();
+        let cbor = signed_message.to_cbor().unwrap();
+
+        // streaming readerが不完全なフレームを渡してくる場合を想定し,
+        // 切り詰められたバッファがpanicせずエラーになることを確認
+        let truncated = &cbor[..cbor.len() / 2];
+        assert!(SuteraSignedMessage::from_cbor(truncated).is_err());
+    }
 }